@@ -0,0 +1,179 @@
+use tonic::client::Grpc;
+use tonic::codec::ProstCodec;
+use tonic::transport::Channel;
+use tonic::Request;
+use tracing::{error, warn};
+
+use crate::setting::SettingWrapper;
+
+/// fully-qualified gRPC method path implemented by the external admission service,
+/// per `proto/admission.proto`
+const CHECK_METHOD: &str = "/relay.EventAdmission/Check";
+
+/// One Nostr tag, e.g. `["e", "<event-id>"]`. Mirrors `TagValues` in `proto/admission.proto`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TagValues {
+    #[prost(string, repeated, tag = "1")]
+    pub values: Vec<String>,
+}
+
+impl From<Vec<String>> for TagValues {
+    fn from(values: Vec<String>) -> Self {
+        Self { values }
+    }
+}
+
+/// Event metadata forwarded to the external admission service for each incoming EVENT.
+/// Mirrors `AdmissionRequest` in `proto/admission.proto`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AdmissionRequest {
+    #[prost(string, tag = "1")]
+    pub pubkey: String,
+    #[prost(uint64, tag = "2")]
+    pub kind: u64,
+    #[prost(message, repeated, tag = "3")]
+    pub tags: Vec<TagValues>,
+    #[prost(uint64, tag = "4")]
+    pub content_size: u64,
+    #[prost(string, optional, tag = "5")]
+    pub client_ip: Option<String>,
+}
+
+/// Decision returned by the external admission service.
+/// Mirrors `AdmissionDecision` in `proto/admission.proto`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AdmissionDecision {
+    #[prost(bool, tag = "1")]
+    pub accepted: bool,
+    /// human-readable message to relay back to the client in the OK response
+    #[prost(string, optional, tag = "2")]
+    pub message: Option<String>,
+}
+
+/// Client for the external event-admission gRPC service configured via
+/// [`crate::setting::Grpc::event_admission_server`].
+///
+/// Used by [`crate::pipeline::WritePipeline`], which the relay's EVENT message
+/// handler calls before persisting a write.
+pub struct AdmissionClient {
+    setting: SettingWrapper,
+}
+
+impl AdmissionClient {
+    pub fn new(setting: SettingWrapper) -> Self {
+        Self { setting }
+    }
+
+    /// Ask the configured admission service whether `request` should be accepted.
+    ///
+    /// If no `event_admission_server` is configured, the event is always accepted.
+    /// If the server is configured but unreachable, the outcome is governed by
+    /// `restricts_write`: fail-closed (rejected) when true, fail-open (accepted)
+    /// when false.
+    pub async fn check(&self, request: AdmissionRequest) -> AdmissionDecision {
+        let (server, restricts_write) = {
+            let setting = self.setting.read();
+            (
+                setting.grpc.event_admission_server.clone(),
+                setting.grpc.restricts_write,
+            )
+        };
+
+        let server = match server {
+            Some(server) => server,
+            None => {
+                return AdmissionDecision {
+                    accepted: true,
+                    message: None,
+                }
+            }
+        };
+
+        match Self::call(&server, request).await {
+            Ok(decision) => decision,
+            Err(e) => {
+                error!(
+                    error = e.to_string(),
+                    server, "event admission server unreachable"
+                );
+                if restricts_write {
+                    AdmissionDecision {
+                        accepted: false,
+                        message: Some("event admission server unreachable".to_string()),
+                    }
+                } else {
+                    warn!(server, "failing open on unreachable event admission server");
+                    AdmissionDecision {
+                        accepted: true,
+                        message: None,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Perform the actual gRPC call to `server`, forwarding `request` and returning
+    /// the service's real accept/reject decision, encoded per `proto/admission.proto`.
+    /// Kept separate from [`Self::check`] so the fail-open/fail-closed policy above
+    /// is independent of the transport.
+    async fn call(server: &str, request: AdmissionRequest) -> crate::Result<AdmissionDecision> {
+        let channel = Channel::from_shared(server.to_string())?.connect().await?;
+
+        let mut client = Grpc::new(channel);
+        client.ready().await?;
+
+        let codec = ProstCodec::<AdmissionRequest, AdmissionDecision>::default();
+        let response = client
+            .unary(Request::new(request), CHECK_METHOD.parse()?, codec)
+            .await?;
+
+        Ok(response.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::setting::Setting;
+    use parking_lot::RwLock;
+    use std::sync::Arc;
+
+    fn sample_request() -> AdmissionRequest {
+        AdmissionRequest {
+            pubkey: "abc123".to_string(),
+            kind: 1,
+            tags: vec![],
+            content_size: 42,
+            client_ip: Some("127.0.0.1".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn no_server_configured_always_accepts() {
+        let client = AdmissionClient::new(Arc::new(RwLock::new(Setting::default())));
+        let decision = client.check(sample_request()).await;
+        assert!(decision.accepted);
+    }
+
+    #[tokio::test]
+    async fn unreachable_server_fails_open_by_default() {
+        let mut setting = Setting::default();
+        setting.grpc.event_admission_server = Some("http://127.0.0.1:1".to_string());
+        setting.grpc.restricts_write = false;
+        let client = AdmissionClient::new(Arc::new(RwLock::new(setting)));
+
+        let decision = client.check(sample_request()).await;
+        assert!(decision.accepted);
+    }
+
+    #[tokio::test]
+    async fn unreachable_server_fails_closed_when_restricted() {
+        let mut setting = Setting::default();
+        setting.grpc.event_admission_server = Some("http://127.0.0.1:1".to_string());
+        setting.grpc.restricts_write = true;
+        let client = AdmissionClient::new(Arc::new(RwLock::new(setting)));
+
+        let decision = client.check(sample_request()).await;
+        assert!(!decision.accepted);
+    }
+}
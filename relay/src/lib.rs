@@ -0,0 +1,13 @@
+pub mod grpc;
+pub mod payment;
+pub mod pipeline;
+pub mod ratelimit;
+pub mod retention;
+pub mod setting;
+mod startup;
+
+pub use pipeline::{EventMeta, WriteDecision, WritePipeline};
+pub use startup::start_background_tasks;
+
+pub type Error = anyhow::Error;
+pub type Result<T> = anyhow::Result<T>;
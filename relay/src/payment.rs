@@ -0,0 +1,221 @@
+use crate::setting::SettingWrapper;
+use parking_lot::RwLock;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Outcome of requesting an admission invoice for a pubkey.
+pub struct Invoice {
+    /// payment URL or invoice string to relay back to the client in the OK response
+    pub payment_url: String,
+    /// processor-assigned identifier used to later confirm payment, e.g. an LNbits payment hash
+    pub payment_hash: String,
+}
+
+/// Backend that can issue invoices and confirm payment for paid-relay admission.
+///
+/// Implemented per `payment.processor` value (e.g. "lnbits") so new processors can
+/// be added without touching the admission gating logic in [`PaymentGate`].
+#[async_trait::async_trait]
+pub trait PaymentProcessor: Send + Sync {
+    /// issue an admission invoice for `pubkey` worth `amount_sats`
+    async fn create_invoice(&self, pubkey: &str, amount_sats: u64) -> crate::Result<Invoice>;
+    /// check whether the invoice identified by `payment_hash` has been paid
+    async fn is_paid(&self, payment_hash: &str) -> crate::Result<bool>;
+}
+
+#[derive(Debug, Deserialize)]
+struct LnbitsInvoiceResponse {
+    payment_hash: String,
+    payment_request: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LnbitsPaymentStatus {
+    paid: bool,
+}
+
+/// LNbits-backed [`PaymentProcessor`], configured via `payment.api_url`/`payment.api_key`.
+pub struct LnbitsProcessor {
+    api_url: String,
+    api_key: String,
+    http: reqwest::Client,
+}
+
+impl LnbitsProcessor {
+    pub fn new(api_url: String, api_key: String) -> Self {
+        Self {
+            api_url,
+            api_key,
+            http: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PaymentProcessor for LnbitsProcessor {
+    async fn create_invoice(&self, pubkey: &str, amount_sats: u64) -> crate::Result<Invoice> {
+        let response: LnbitsInvoiceResponse = self
+            .http
+            .post(format!("{}/api/v1/payments", self.api_url))
+            .header("X-Api-Key", &self.api_key)
+            .json(&serde_json::json!({
+                "out": false,
+                "amount": amount_sats,
+                "memo": format!("rnostr admission for {pubkey}"),
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(Invoice {
+            payment_url: response.payment_request,
+            payment_hash: response.payment_hash,
+        })
+    }
+
+    async fn is_paid(&self, payment_hash: &str) -> crate::Result<bool> {
+        let status: LnbitsPaymentStatus = self
+            .http
+            .get(format!("{}/api/v1/payments/{}", self.api_url, payment_hash))
+            .header("X-Api-Key", &self.api_key)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(status.paid)
+    }
+}
+
+/// Gates event writes on whether the author has paid relay admission, per
+/// [`crate::setting::Payment`].
+///
+/// Used by [`crate::pipeline::WritePipeline`], which the relay's EVENT message handler
+/// calls before persisting a write, replying with the returned invoice's `payment_url`
+/// in the OK message when `check` returns `Some`.
+pub struct PaymentGate<P: PaymentProcessor> {
+    setting: SettingWrapper,
+    processor: P,
+    /// pubkeys confirmed as admitted; checked before falling back to the processor
+    admitted: RwLock<std::collections::HashSet<String>>,
+    /// payment hash of the outstanding admission invoice for each pending pubkey
+    pending: RwLock<HashMap<String, String>>,
+}
+
+impl<P: PaymentProcessor> PaymentGate<P> {
+    pub fn new(setting: SettingWrapper, processor: P) -> Self {
+        Self {
+            setting,
+            processor,
+            admitted: RwLock::new(std::collections::HashSet::new()),
+            pending: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Check whether `pubkey` may publish. Returns `Ok(None)` if admitted, or
+    /// `Ok(Some(invoice))` if the caller should reply with OK=false and the invoice.
+    pub async fn check(&self, pubkey: &str) -> crate::Result<Option<Invoice>> {
+        if !self.setting.read().payment.enabled {
+            return Ok(None);
+        }
+        if self.admitted.read().contains(pubkey) {
+            return Ok(None);
+        }
+
+        let pending_hash = self.pending.read().get(pubkey).cloned();
+        if let Some(payment_hash) = pending_hash {
+            if self.processor.is_paid(&payment_hash).await? {
+                self.admitted.write().insert(pubkey.to_string());
+                self.pending.write().remove(pubkey);
+                return Ok(None);
+            }
+        }
+
+        let admission_cost_sats = self.setting.read().payment.admission_cost_sats;
+        let invoice = self
+            .processor
+            .create_invoice(pubkey, admission_cost_sats)
+            .await?;
+        self.pending
+            .write()
+            .insert(pubkey.to_string(), invoice.payment_hash.clone());
+        Ok(Some(invoice))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::setting::Setting;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// in-memory [`PaymentProcessor`] for exercising [`PaymentGate`] without a network call
+    struct MockProcessor {
+        paid: AtomicBool,
+    }
+
+    #[async_trait::async_trait]
+    impl PaymentProcessor for MockProcessor {
+        async fn create_invoice(&self, pubkey: &str, amount_sats: u64) -> crate::Result<Invoice> {
+            Ok(Invoice {
+                payment_url: format!("mock://invoice/{pubkey}/{amount_sats}"),
+                payment_hash: format!("hash-{pubkey}"),
+            })
+        }
+
+        async fn is_paid(&self, _payment_hash: &str) -> crate::Result<bool> {
+            Ok(self.paid.load(Ordering::SeqCst))
+        }
+    }
+
+    fn wrap(setting: Setting) -> SettingWrapper {
+        std::sync::Arc::new(parking_lot::RwLock::new(setting))
+    }
+
+    fn paid_setting() -> SettingWrapper {
+        let mut setting = Setting::default();
+        setting.payment.enabled = true;
+        setting.payment.admission_cost_sats = 100;
+        wrap(setting)
+    }
+
+    #[tokio::test]
+    async fn disabled_always_admits() {
+        let gate = PaymentGate::new(
+            wrap(Setting::default()),
+            MockProcessor {
+                paid: AtomicBool::new(false),
+            },
+        );
+        assert!(gate.check("alice").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn unpaid_pubkey_gets_an_invoice() {
+        let gate = PaymentGate::new(
+            paid_setting(),
+            MockProcessor {
+                paid: AtomicBool::new(false),
+            },
+        );
+        let invoice = gate.check("alice").await.unwrap();
+        assert!(invoice.is_some());
+    }
+
+    #[tokio::test]
+    async fn paid_pubkey_is_admitted_on_next_check() {
+        let gate = PaymentGate::new(
+            paid_setting(),
+            MockProcessor {
+                paid: AtomicBool::new(false),
+            },
+        );
+        assert!(gate.check("alice").await.unwrap().is_some());
+
+        gate.processor.paid.store(true, Ordering::SeqCst);
+        assert!(gate.check("alice").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,162 @@
+use crate::grpc::{AdmissionClient, AdmissionRequest};
+use crate::payment::{LnbitsProcessor, PaymentGate};
+use crate::ratelimit::WriteRateLimiter;
+use crate::setting::SettingWrapper;
+use tracing::error;
+
+/// Metadata for an incoming EVENT, gathered by the session handler before it is
+/// admitted into the write pipeline.
+pub struct EventMeta {
+    pub pubkey: String,
+    pub kind: u64,
+    pub tags: Vec<Vec<String>>,
+    pub content_size: usize,
+    pub client_ip: Option<String>,
+}
+
+/// Outcome of running an [`EventMeta`] through [`WritePipeline::admit`].
+pub enum WriteDecision {
+    /// the event may be persisted
+    Accept,
+    /// the event must not be persisted; relay this reason back in the OK response
+    Reject(String),
+}
+
+/// Runs every write-time admission check for an incoming EVENT, in the order a
+/// client would observe them failing: rate limit, external gRPC admission, then
+/// paid-relay admission. Called from the relay's EVENT message handler, before the
+/// event is written to storage.
+pub struct WritePipeline {
+    rate_limiter: WriteRateLimiter,
+    admission: AdmissionClient,
+    payment: PaymentGate<LnbitsProcessor>,
+}
+
+impl WritePipeline {
+    pub fn new(setting: SettingWrapper) -> Self {
+        let processor = {
+            let setting = setting.read();
+            LnbitsProcessor::new(
+                setting.payment.api_url.clone().unwrap_or_default(),
+                setting.payment.api_key.clone().unwrap_or_default(),
+            )
+        };
+        Self {
+            rate_limiter: WriteRateLimiter::new(setting.clone()),
+            admission: AdmissionClient::new(setting.clone()),
+            payment: PaymentGate::new(setting, processor),
+        }
+    }
+
+    pub async fn admit(&self, event: &EventMeta) -> WriteDecision {
+        if !self.rate_limiter.try_acquire() {
+            return WriteDecision::Reject("rate-limited".to_string());
+        }
+
+        let decision = self
+            .admission
+            .check(AdmissionRequest {
+                pubkey: event.pubkey.clone(),
+                kind: event.kind,
+                tags: event.tags.iter().cloned().map(Into::into).collect(),
+                content_size: event.content_size as u64,
+                client_ip: event.client_ip.clone(),
+            })
+            .await;
+        if !decision.accepted {
+            return WriteDecision::Reject(
+                decision
+                    .message
+                    .unwrap_or_else(|| "rejected by event admission policy".to_string()),
+            );
+        }
+
+        match self.payment.check(&event.pubkey).await {
+            Ok(Some(invoice)) => return WriteDecision::Reject(invoice.payment_url),
+            Ok(None) => {}
+            Err(e) => {
+                error!(error = e.to_string(), "payment processor error");
+                return WriteDecision::Reject("payment processor error".to_string());
+            }
+        }
+
+        WriteDecision::Accept
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::setting::Setting;
+    use parking_lot::RwLock;
+    use std::sync::Arc;
+
+    fn sample_event() -> EventMeta {
+        EventMeta {
+            pubkey: "abc123".to_string(),
+            kind: 1,
+            tags: vec![],
+            content_size: 42,
+            client_ip: Some("127.0.0.1".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn unthrottled_writes_are_accepted() {
+        let pipeline = WritePipeline::new(Arc::new(RwLock::new(Setting::default())));
+        assert!(matches!(
+            pipeline.admit(&sample_event()).await,
+            WriteDecision::Accept
+        ));
+    }
+
+    #[tokio::test]
+    async fn unreachable_admission_server_fails_closed() {
+        let mut setting = Setting::default();
+        setting.grpc.event_admission_server = Some("http://127.0.0.1:1".to_string());
+        setting.grpc.restricts_write = true;
+        let pipeline = WritePipeline::new(Arc::new(RwLock::new(setting)));
+
+        assert!(matches!(
+            pipeline.admit(&sample_event()).await,
+            WriteDecision::Reject(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn exhausted_rate_limit_rejects_writes() {
+        let mut setting = Setting::default();
+        setting.limitation.messages_per_sec = Some(1);
+        let pipeline = WritePipeline::new(Arc::new(RwLock::new(setting)));
+
+        let mut rejected = false;
+        for _ in 0..10_000 {
+            if matches!(
+                pipeline.admit(&sample_event()).await,
+                WriteDecision::Reject(_)
+            ) {
+                rejected = true;
+                break;
+            }
+        }
+        assert!(
+            rejected,
+            "sustained writes should eventually be rate-limited"
+        );
+    }
+
+    #[tokio::test]
+    async fn unpaid_pubkey_is_rejected() {
+        // no api_url is configured, so the processor call fails and the write is
+        // rejected with a processor error rather than an invoice
+        let mut setting = Setting::default();
+        setting.payment.enabled = true;
+        setting.payment.admission_cost_sats = 100;
+        let pipeline = WritePipeline::new(Arc::new(RwLock::new(setting)));
+
+        match pipeline.admit(&sample_event()).await {
+            WriteDecision::Reject(message) => assert!(!message.is_empty()),
+            WriteDecision::Accept => panic!("expected payment to be required"),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::setting::SettingWrapper;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// burst cap as a multiple of `messages_per_sec`, so short spikes don't get throttled
+const BURST_SECONDS: u64 = 5;
+
+/// Token-bucket limiter enforcing [`crate::setting::Limitation::messages_per_sec`].
+///
+/// The bucket refills continuously at `messages_per_sec` permits per second, up to a
+/// burst cap, and is re-read from `setting` on every permit check so config reloads
+/// via [`crate::setting::Setting::watch`] take effect immediately.
+///
+/// Used by [`crate::pipeline::WritePipeline`], which the relay's EVENT message
+/// handler calls before persisting a write.
+pub struct WriteRateLimiter {
+    setting: SettingWrapper,
+    /// available permits, scaled by `SCALE` to allow sub-permit refill precision
+    permits: AtomicI64,
+    /// last refill time, in milliseconds since the epoch
+    last_refill_ms: AtomicU64,
+}
+
+/// fixed-point scale used to track fractional permits between refills
+const SCALE: i64 = 1000;
+
+impl WriteRateLimiter {
+    pub fn new(setting: SettingWrapper) -> Self {
+        let initial_permits = match setting.read().limitation.messages_per_sec {
+            Some(rate) if rate > 0 => rate as i64 * BURST_SECONDS as i64 * SCALE,
+            _ => SCALE,
+        };
+        Self {
+            setting,
+            permits: AtomicI64::new(initial_permits),
+            last_refill_ms: AtomicU64::new(now_ms()),
+        }
+    }
+
+    /// Try to consume one permit for an event write.
+    ///
+    /// Returns `true` if the write is allowed. Returns `false` if the bucket is empty,
+    /// meaning the caller should reject the write with an OK=false "rate-limited" message
+    /// (or delay and retry) rather than persisting it.
+    pub fn try_acquire(&self) -> bool {
+        let messages_per_sec = match self.setting.read().limitation.messages_per_sec {
+            Some(rate) if rate > 0 => rate as i64,
+            _ => return true,
+        };
+
+        let now = now_ms();
+        let last = self.last_refill_ms.swap(now, Ordering::AcqRel);
+        let elapsed_ms = now.saturating_sub(last) as i64;
+        let refill = elapsed_ms * messages_per_sec * SCALE / 1000;
+        let burst_cap = messages_per_sec * BURST_SECONDS as i64 * SCALE;
+
+        let mut current = self.permits.load(Ordering::Acquire);
+        loop {
+            let refilled = (current + refill).min(burst_cap);
+            if refilled < SCALE {
+                self.permits.store(refilled, Ordering::Release);
+                return false;
+            }
+            match self.permits.compare_exchange_weak(
+                current,
+                refilled - SCALE,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return true,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::setting::Setting;
+    use parking_lot::RwLock;
+    use std::sync::Arc;
+
+    #[test]
+    fn disabled_always_allows() {
+        let limiter = WriteRateLimiter::new(Arc::new(RwLock::new(Setting::default())));
+        for _ in 0..1000 {
+            assert!(limiter.try_acquire());
+        }
+    }
+
+    #[test]
+    fn fresh_limiter_allows_initial_burst() {
+        let mut setting = Setting::default();
+        setting.limitation.messages_per_sec = Some(10);
+        let limiter = WriteRateLimiter::new(Arc::new(RwLock::new(setting)));
+
+        // a freshly constructed limiter must not reject the very first write
+        assert!(limiter.try_acquire());
+    }
+
+    #[test]
+    fn empty_bucket_rejects() {
+        let mut setting = Setting::default();
+        setting.limitation.messages_per_sec = Some(1);
+        let limiter = WriteRateLimiter::new(Arc::new(RwLock::new(setting)));
+
+        let mut rejected = false;
+        for _ in 0..(BURST_SECONDS as i64 * SCALE + 10) {
+            if !limiter.try_acquire() {
+                rejected = true;
+                break;
+            }
+        }
+        assert!(
+            rejected,
+            "bucket should eventually run dry under sustained use"
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use crate::setting::SettingWrapper;
+use std::time::Duration;
+use tokio::time::interval;
+use tracing::{error, info};
+
+/// How often the pruning task checks whether any retention bound is exceeded
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Storage operations required to enforce [`crate::setting::Retention`] limits.
+///
+/// Implemented by the event store so the pruning task can stay agnostic of
+/// the underlying database engine.
+pub trait RetentionStore {
+    /// delete the oldest non-whitelisted events by `created_at` until `count` remain
+    fn prune_to_count(
+        &self,
+        count: usize,
+        whitelist_pubkeys: &[String],
+    ) -> Result<u64, crate::Error>;
+    /// delete the oldest non-whitelisted events by `created_at` until total size is under `bytes`
+    fn prune_to_bytes(&self, bytes: u64, whitelist_pubkeys: &[String])
+        -> Result<u64, crate::Error>;
+    /// delete non-whitelisted events with `created_at` older than `before`
+    fn prune_older_than(
+        &self,
+        before: u64,
+        whitelist_pubkeys: &[String],
+    ) -> Result<u64, crate::Error>;
+}
+
+/// Spawn the background task that periodically prunes stored events to keep
+/// them within the configured [`crate::setting::Retention`] bounds.
+///
+/// Re-reads `setting` on every tick so limits tuned via [`crate::setting::Setting::watch`]
+/// take effect without restarting the relay.
+///
+/// Called from [`crate::start_background_tasks`] at startup, once the event store exists.
+pub fn spawn_prune_task<S: RetentionStore + Send + Sync + 'static>(
+    setting: SettingWrapper,
+    store: S,
+) {
+    tokio::spawn(async move {
+        let mut ticker = interval(CHECK_INTERVAL);
+        loop {
+            ticker.tick().await;
+            prune_once(&setting.read().retention.clone(), &store);
+        }
+    });
+}
+
+/// Run one pruning pass against `store` for the bounds configured in `retention`.
+///
+/// Split out from [`spawn_prune_task`] so the pruning logic can be exercised directly
+/// in tests without waiting on the ticker.
+fn prune_once<S: RetentionStore>(retention: &crate::setting::Retention, store: &S) {
+    let whitelist = retention.whitelist_pubkeys.clone().unwrap_or_default();
+
+    if let Some(max_events) = retention.max_events {
+        match store.prune_to_count(max_events, &whitelist) {
+            Ok(0) => {}
+            Ok(n) => info!(deleted = n, max_events, "pruned events over max_events"),
+            Err(e) => error!(error = e.to_string(), "failed to prune by max_events"),
+        }
+    }
+
+    if let Some(max_bytes) = retention.max_bytes {
+        match store.prune_to_bytes(max_bytes, &whitelist) {
+            Ok(0) => {}
+            Ok(n) => info!(deleted = n, max_bytes, "pruned events over max_bytes"),
+            Err(e) => error!(error = e.to_string(), "failed to prune by max_bytes"),
+        }
+    }
+
+    if let Some(persist_days) = retention.persist_days {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let before = now.saturating_sub(persist_days * 86400);
+        match store.prune_older_than(before, &whitelist) {
+            Ok(0) => {}
+            Ok(n) => info!(
+                deleted = n,
+                persist_days, "pruned events older than persist_days"
+            ),
+            Err(e) => error!(error = e.to_string(), "failed to prune by persist_days"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::setting::Retention;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    /// in-memory [`RetentionStore`] recording which prune method was called and how much
+    #[derive(Default)]
+    struct FakeStore {
+        deleted: AtomicUsize,
+        prune_to_count_calls: Mutex<Vec<usize>>,
+        prune_to_bytes_calls: Mutex<Vec<u64>>,
+        prune_older_than_calls: Mutex<Vec<u64>>,
+    }
+
+    impl RetentionStore for FakeStore {
+        fn prune_to_count(&self, count: usize, _whitelist: &[String]) -> Result<u64, crate::Error> {
+            self.prune_to_count_calls.lock().unwrap().push(count);
+            self.deleted.fetch_add(1, Ordering::SeqCst);
+            Ok(1)
+        }
+
+        fn prune_to_bytes(&self, bytes: u64, _whitelist: &[String]) -> Result<u64, crate::Error> {
+            self.prune_to_bytes_calls.lock().unwrap().push(bytes);
+            Ok(0)
+        }
+
+        fn prune_older_than(
+            &self,
+            before: u64,
+            _whitelist: &[String],
+        ) -> Result<u64, crate::Error> {
+            self.prune_older_than_calls.lock().unwrap().push(before);
+            Ok(0)
+        }
+    }
+
+    #[test]
+    fn unset_bounds_prune_nothing() {
+        let store = FakeStore::default();
+        prune_once(&Retention::default(), &store);
+        assert!(store.prune_to_count_calls.lock().unwrap().is_empty());
+        assert!(store.prune_to_bytes_calls.lock().unwrap().is_empty());
+        assert!(store.prune_older_than_calls.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn max_events_triggers_prune_to_count() {
+        let store = FakeStore::default();
+        let retention = Retention {
+            max_events: Some(1000),
+            ..Default::default()
+        };
+        prune_once(&retention, &store);
+        assert_eq!(
+            store.prune_to_count_calls.lock().unwrap().as_slice(),
+            &[1000]
+        );
+    }
+
+    #[test]
+    fn persist_days_prunes_before_now_minus_days() {
+        let store = FakeStore::default();
+        let retention = Retention {
+            persist_days: Some(1),
+            ..Default::default()
+        };
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        prune_once(&retention, &store);
+
+        let calls = store.prune_older_than_calls.lock().unwrap();
+        assert_eq!(calls.len(), 1);
+        assert!(calls[0] >= now - 86400 && calls[0] < now - 86400 + 10);
+    }
+}
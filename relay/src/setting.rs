@@ -1,5 +1,5 @@
 use crate::Result;
-use config::{Config, File};
+use config::{Config, Environment, File};
 use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
@@ -15,7 +15,20 @@ pub struct Information {
     pub description: Option<String>,
     pub pubkey: Option<String>,
     pub contact: Option<String>,
-    // supported_nips, software, version
+    /// NIP numbers supported by this relay
+    pub supported_nips: Option<Vec<u32>>,
+    /// relay software, e.g. a repository URL
+    pub software: Option<String>,
+    /// relay software version
+    pub version: Option<String>,
+    /// canonical websocket URL of this relay
+    pub relay_url: Option<String>,
+    /// URL of a favicon for the relay
+    pub favicon: Option<String>,
+    /// URL of an icon for the relay
+    pub icon: Option<String>,
+    /// NIP-11 fee schedule, populated from `payment` when paid-relay mode is enabled
+    pub fees: Option<Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -76,6 +89,19 @@ impl Default for Network {
     }
 }
 
+/// data retention config, used to bound disk usage on long-running relays
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Retention {
+    /// delete the oldest events until the total number of stored events is under this bound
+    pub max_events: Option<usize>,
+    /// delete the oldest events until the total size of stored events is under this bound
+    pub max_bytes: Option<u64>,
+    /// delete events older than this many days
+    pub persist_days: Option<u64>,
+    /// events authored by these pubkeys are never pruned
+    pub whitelist_pubkeys: Option<Vec<String>>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Limitation {
     /// this is the maximum number of bytes for incoming JSON. default 64K
@@ -96,6 +122,9 @@ pub struct Limitation {
     pub max_event_time_older_than_now: u64,
     /// Events newer than this will be rejected. default 15 minutes
     pub max_event_time_newer_than_now: u64,
+    /// relay-wide ceiling on how many events may be persisted per second, averaged over a one-minute window.
+    /// `None` disables write rate limiting. default None
+    pub messages_per_sec: Option<u32>,
 }
 
 impl Default for Limitation {
@@ -110,10 +139,50 @@ impl Default for Limitation {
             max_event_tags: 5000,
             max_event_time_older_than_now: 94608000,
             max_event_time_newer_than_now: 900,
+            messages_per_sec: None,
         }
     }
 }
 
+/// external gRPC event-admission hook, allowing write policy (spam filtering,
+/// allow-lists, payment checks, ...) to be implemented without forking the relay
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Grpc {
+    /// address of the external event-admission gRPC service, e.g. "http://127.0.0.1:50051"
+    pub event_admission_server: Option<String>,
+    /// when true, events are rejected (fail-closed) if the admission server is unreachable;
+    /// when false, events are accepted (fail-open) on admission server errors
+    pub restricts_write: bool,
+}
+
+/// diagnostics config, read once at startup since console instrumentation can't be
+/// toggled mid-run
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Diagnostics {
+    /// install the `console-subscriber` layer so tokio-console can attach and inspect
+    /// task/poll behavior of the reader and http thread pools
+    pub tracing: bool,
+}
+
+/// paid-relay config, gating writes on a per-pubkey admission payment
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Payment {
+    /// require payment before an unknown pubkey's events are accepted
+    pub enabled: bool,
+    /// payment processor backend name, e.g. "lnbits"; `WritePipeline` does not read this
+    /// value and always uses the LNbits processor
+    pub processor: String,
+    /// sats required once per pubkey before it may publish
+    pub admission_cost_sats: u64,
+    /// per-event publication fee in sats, advertised in the NIP-11 `fees` block;
+    /// `PaymentGate` charges only admission and does not collect this fee
+    pub cost_per_event_sats: u64,
+    /// processor API base URL
+    pub api_url: Option<String>,
+    /// processor API key
+    pub api_key: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Setting {
     pub information: Information,
@@ -121,6 +190,10 @@ pub struct Setting {
     pub thread: Thread,
     pub network: Network,
     pub limitation: Limitation,
+    pub retention: Retention,
+    pub grpc: Grpc,
+    pub diagnostics: Diagnostics,
+    pub payment: Payment,
 
     /// flatten extensions setting
     #[serde(flatten)]
@@ -134,9 +207,43 @@ impl Setting {
         Arc::new(RwLock::new(Self::default()))
     }
 
-    /// information json
+    /// NIP-11 relay information document, spec-complete including the live `limitation`
+    /// block derived from `self.limitation` so clients can discover the relay's actual limits
     pub fn render_information(&self) -> Result<String> {
-        Ok(serde_json::to_string_pretty(&self.information)?)
+        let mut information = self.information.clone();
+        if self.payment.enabled {
+            information.fees = Some(serde_json::json!({
+                "admission": [{ "amount": self.payment.admission_cost_sats * 1000, "unit": "msats" }],
+                "publication": [{ "amount": self.payment.cost_per_event_sats * 1000, "unit": "msats" }],
+            }));
+        }
+
+        let mut document = serde_json::to_value(&information)?;
+        let limitation = serde_json::json!({
+            "max_message_length": self.limitation.max_message_length,
+            "max_subscriptions": self.limitation.max_subscriptions,
+            "max_filters": self.limitation.max_filters,
+            "max_limit": self.limitation.max_limit,
+            "max_subid_length": self.limitation.max_subid_length,
+            "min_prefix": self.limitation.min_prefix,
+            "max_event_tags": self.limitation.max_event_tags,
+            "created_at_lower_limit": self.limitation.max_event_time_older_than_now,
+            "created_at_upper_limit": self.limitation.max_event_time_newer_than_now,
+        });
+        document["limitation"] = limitation;
+
+        Ok(serde_json::to_string_pretty(&document)?)
+    }
+
+    /// Install the `console-subscriber` layer if `diagnostics.tracing` is enabled.
+    ///
+    /// Must be called once at startup, before any other tracing subscriber is installed.
+    /// Unlike the rest of `Setting`, this is not re-applied on config reload.
+    /// Called from [`crate::start_background_tasks`].
+    pub fn init_diagnostics(&self) {
+        if self.diagnostics.tracing {
+            console_subscriber::init();
+        }
     }
 
     pub fn read_wrapper<P: AsRef<Path>>(file: P) -> Result<SettingWrapper> {
@@ -152,6 +259,12 @@ impl Setting {
             .add_source(Config::try_from(&def)?)
             // override with file contents
             .add_source(File::with_name(file.as_ref().to_str().unwrap()))
+            // override with environment variables, e.g. RNOSTR__NETWORK__PORT=8080
+            .add_source(
+                Environment::with_prefix("RNOSTR")
+                    .separator("__")
+                    .try_parsing(true),
+            )
             .build()?;
 
         let setting: Setting = config.try_deserialize()?;
@@ -244,4 +357,69 @@ mod tests {
         assert_eq!(setting.read().information.name, Some("nostr".to_string()));
         Ok(())
     }
+
+    #[test]
+    fn read_env_override() -> Result<()> {
+        let file = Builder::new()
+            .prefix("nostr-relay-config-test-env")
+            .suffix(".toml")
+            .rand_bytes(0)
+            .tempfile()?;
+        fs::write(
+            &file,
+            r#"[network]
+        port = 1234
+        "#,
+        )?;
+
+        std::env::set_var("RNOSTR__NETWORK__PORT", "4321");
+        let setting = Setting::read(&file);
+        std::env::remove_var("RNOSTR__NETWORK__PORT");
+
+        assert_eq!(setting?.network.port, 4321);
+        Ok(())
+    }
+
+    #[test]
+    fn render_information_embeds_limitation() -> Result<()> {
+        let setting = Setting::default();
+        let document: Value = serde_json::from_str(&setting.render_information()?)?;
+
+        assert_eq!(
+            document["limitation"]["max_message_length"],
+            setting.limitation.max_message_length
+        );
+        assert_eq!(
+            document["limitation"]["max_subscriptions"],
+            setting.limitation.max_subscriptions
+        );
+        assert_eq!(
+            document["limitation"]["created_at_lower_limit"],
+            setting.limitation.max_event_time_older_than_now
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn render_information_includes_fees_only_when_payment_enabled() -> Result<()> {
+        let mut setting = Setting::default();
+        let document: Value = serde_json::from_str(&setting.render_information()?)?;
+        assert!(document.get("fees").unwrap().is_null());
+
+        setting.payment.enabled = true;
+        setting.payment.admission_cost_sats = 10;
+        let document: Value = serde_json::from_str(&setting.render_information()?)?;
+        assert!(document["fees"]["admission"].is_array());
+        Ok(())
+    }
+
+    #[test]
+    fn init_diagnostics_is_a_noop_when_disabled() {
+        // with tracing disabled, init_diagnostics must not attempt to install
+        // console-subscriber, which can only be installed once per process
+        let setting = Setting::default();
+        assert!(!setting.diagnostics.tracing);
+        setting.init_diagnostics();
+        setting.init_diagnostics();
+    }
 }
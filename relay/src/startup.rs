@@ -0,0 +1,46 @@
+use crate::retention::{spawn_prune_task, RetentionStore};
+use crate::setting::SettingWrapper;
+
+/// Run the relay's one-time startup hooks and spawn its background maintenance tasks.
+///
+/// Called once from the binary entrypoint, after the event store is constructed and
+/// passed in as `store`.
+pub fn start_background_tasks<S: RetentionStore + Send + Sync + 'static>(
+    setting: SettingWrapper,
+    store: S,
+) {
+    // must run before any other tracing subscriber is installed, and only once,
+    // so it comes first and is not re-run on config reload
+    setting.read().init_diagnostics();
+
+    spawn_prune_task(setting, store);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::setting::Setting;
+    use parking_lot::RwLock;
+    use std::sync::Arc;
+
+    struct NoopStore;
+
+    impl RetentionStore for NoopStore {
+        fn prune_to_count(&self, _count: usize, _whitelist: &[String]) -> crate::Result<u64> {
+            Ok(0)
+        }
+
+        fn prune_to_bytes(&self, _bytes: u64, _whitelist: &[String]) -> crate::Result<u64> {
+            Ok(0)
+        }
+
+        fn prune_older_than(&self, _before: u64, _whitelist: &[String]) -> crate::Result<u64> {
+            Ok(0)
+        }
+    }
+
+    #[tokio::test]
+    async fn spawns_without_panicking() {
+        start_background_tasks(Arc::new(RwLock::new(Setting::default())), NoopStore);
+    }
+}